@@ -0,0 +1,131 @@
+use crate::{
+    completion_stream_len::CompletionStreamLen, completion_stream_position::CompletionStreamPosition,
+};
+use completion_core::CompletionFuture;
+use std::io::{self, SeekFrom};
+
+/// Types that can seek using a completion-based future.
+///
+/// Unlike `futures::io::AsyncSeek`'s poll-based `poll_seek` or
+/// `tokio::io::AsyncSeek`'s two-phase `start_seek`/`poll_complete`, a seek
+/// here is a single [`CompletionFuture`] that must be driven to completion
+/// and, per that trait's contract, may not be dropped while in flight.
+///
+/// The lifetime parameter lets implementors hand out a `SeekFuture` that
+/// borrows `self` for exactly the duration of one seek, so a caller can
+/// issue several seeks in sequence against the same stream.
+pub trait AsyncSeekWith<'a> {
+    type SeekFuture: CompletionFuture<Output = io::Result<u64>> + 'a;
+
+    fn seek(&'a mut self, pos: SeekFrom) -> Self::SeekFuture;
+}
+
+/// Adds convenience methods to all types that implement [`AsyncSeekWith`]
+/// for every lifetime.
+pub trait CompletionSeekExt: for<'a> AsyncSeekWith<'a> {
+    fn stream_position(&mut self) -> CompletionStreamPosition<'_, Self> {
+        CompletionStreamPosition::new(self)
+    }
+
+    fn stream_len(&mut self) -> CompletionStreamLen<'_, Self> {
+        CompletionStreamLen::new(self)
+    }
+}
+
+impl<S: for<'a> AsyncSeekWith<'a> + ?Sized> CompletionSeekExt for S {}
+
+#[cfg(test)]
+mod tests {
+    use super::{AsyncSeekWith, CompletionSeekExt};
+    use completion_core::CompletionFuture;
+    use std::{
+        io::{self, Cursor, Seek, SeekFrom},
+        pin::Pin,
+        task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+    };
+
+    /// A seek future that resolves on its very first poll, wrapping a
+    /// synchronous `std::io::Seek`.
+    ///
+    /// `pub` (despite living in a test module) because it is named as the
+    /// `AsyncSeekWith::SeekFuture` associated type of an impl on the public
+    /// `Cursor<Vec<u8>>` type below, and a private type can't appear in a
+    /// public trait impl's interface.
+    pub struct ImmediateSeek<'a> {
+        cursor: &'a mut Cursor<Vec<u8>>,
+        pos: SeekFrom,
+    }
+
+    impl CompletionFuture for ImmediateSeek<'_> {
+        type Output = io::Result<u64>;
+
+        unsafe fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let this = unsafe { self.get_unchecked_mut() };
+            Poll::Ready(Seek::seek(this.cursor, this.pos))
+        }
+
+        unsafe fn poll_cancel(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+            Poll::Ready(())
+        }
+    }
+
+    impl<'a> AsyncSeekWith<'a> for Cursor<Vec<u8>> {
+        type SeekFuture = ImmediateSeek<'a>;
+
+        fn seek(&'a mut self, pos: SeekFrom) -> Self::SeekFuture {
+            ImmediateSeek { cursor: self, pos }
+        }
+    }
+
+    fn block_on<F: CompletionFuture>(mut future: F) -> F::Output {
+        fn noop(_: *const ()) {}
+        fn clone(_: *const ()) -> RawWaker {
+            RawWaker::new(std::ptr::null(), &VTABLE)
+        }
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+        let waker = unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) };
+        let mut cx = Context::from_waker(&waker);
+
+        // SAFETY: `future` is never moved again after this point.
+        let mut future = unsafe { Pin::new_unchecked(&mut future) };
+        loop {
+            // SAFETY: `future` is driven to `Ready` before being dropped.
+            match unsafe { future.as_mut().poll(&mut cx) } {
+                Poll::Ready(output) => return output,
+                Poll::Pending => {}
+            }
+        }
+    }
+
+    #[test]
+    fn stream_len() -> io::Result<()> {
+        let mut c = Cursor::new(vec![0; 15]);
+        assert_eq!(block_on(c.stream_len())?, 15);
+
+        Seek::seek(&mut c, SeekFrom::End(0))?;
+        let old_pos = block_on(c.stream_position())?;
+        assert_eq!(block_on(c.stream_len())?, 15);
+        assert_eq!(block_on(c.stream_position())?, old_pos);
+
+        Seek::seek(&mut c, SeekFrom::Start(7))?;
+        Seek::seek(&mut c, SeekFrom::Current(2))?;
+        let old_pos = block_on(c.stream_position())?;
+        assert_eq!(block_on(c.stream_len())?, 15);
+        assert_eq!(block_on(c.stream_position())?, old_pos);
+        Ok(())
+    }
+
+    #[test]
+    fn stream_position() -> io::Result<()> {
+        let mut c = Cursor::new(vec![0; 15]);
+        assert_eq!(block_on(c.stream_position())?, 0);
+
+        Seek::seek(&mut c, SeekFrom::End(0))?;
+        assert_eq!(block_on(c.stream_position())?, 15);
+
+        Seek::seek(&mut c, SeekFrom::Start(7))?;
+        Seek::seek(&mut c, SeekFrom::Current(2))?;
+        assert_eq!(block_on(c.stream_position())?, 9);
+        Ok(())
+    }
+}
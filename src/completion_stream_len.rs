@@ -0,0 +1,260 @@
+use crate::completion_async_seek_ext::AsyncSeekWith;
+use completion_core::CompletionFuture;
+use std::{
+    io::{self, SeekFrom},
+    marker::PhantomData,
+    pin::Pin,
+    ptr::NonNull,
+    task::{Context, Poll},
+};
+
+/// The phase of the seek dance `CompletionStreamLen` is currently driving,
+/// together with the in-flight future for that phase.
+///
+/// Per [`CompletionFuture`]'s contract, a phase's future must be polled to
+/// completion (and never dropped while in flight) before the next phase's
+/// future is created, so these never overlap.
+enum Phase<F> {
+    Position(F),
+    SeekingEnd { future: F, old_pos: u64 },
+    Restoring { future: F, len: u64 },
+    /// Holds no future; set between dropping one phase's finished future
+    /// and creating the next one.
+    Switching,
+}
+
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct CompletionStreamLen<'a, S: for<'b> AsyncSeekWith<'b> + ?Sized> {
+    // A raw pointer rather than `&'a mut S` because each phase needs its own
+    // fresh reborrow of `S` to call `AsyncSeekWith::seek` again; see
+    // `reborrow` below for the safety argument. Every seek, including the
+    // first, is issued through a reborrow of this pointer so that the
+    // original `&'a mut S` passed to `new` is never also used directly,
+    // which would otherwise invalidate the pointer's provenance.
+    seek: NonNull<S>,
+    phase: Phase<<S as AsyncSeekWith<'a>>::SeekFuture>,
+    _marker: PhantomData<&'a mut S>,
+}
+
+impl<'a, S: for<'b> AsyncSeekWith<'b> + ?Sized> CompletionStreamLen<'a, S> {
+    pub(super) fn new(seek: &'a mut S) -> Self {
+        let ptr = NonNull::from(seek);
+
+        // SAFETY: `ptr` was just derived from the `&'a mut S` passed in, and
+        // that reference is never used again (only `ptr`, from here on), so
+        // this reborrow does not alias a still-live reference.
+        let future =
+            <S as AsyncSeekWith<'a>>::seek(unsafe { &mut *ptr.as_ptr() }, SeekFrom::Current(0));
+
+        Self {
+            seek: ptr,
+            phase: Phase::Position(future),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Materializes a fresh exclusive borrow of the underlying stream to
+    /// start the next phase's seek.
+    ///
+    /// # Safety invariant
+    ///
+    /// This must only be called once the previous phase's future (which
+    /// itself borrowed `S` for `'a`) has run to completion and been dropped,
+    /// so that the borrow handed out here never aliases a still-live one.
+    /// `poll`, below, upholds this by always finishing and discarding the
+    /// current phase's future before reborrowing for the next phase.
+    fn reborrow(&mut self) -> &'a mut S {
+        unsafe { &mut *self.seek.as_ptr() }
+    }
+}
+
+// The `NonNull<S>` above is just a raw-pointer stand-in for the `&'a mut S`
+// this struct logically owns (see `reborrow`), so it's sound to be `Send`
+// or `Sync` wherever that reference, and each phase's future, would be.
+unsafe impl<'a, S> Send for CompletionStreamLen<'a, S>
+where
+    S: for<'b> AsyncSeekWith<'b> + ?Sized + Send,
+    <S as AsyncSeekWith<'a>>::SeekFuture: Send,
+{
+}
+
+unsafe impl<'a, S> Sync for CompletionStreamLen<'a, S>
+where
+    S: for<'b> AsyncSeekWith<'b> + ?Sized + Sync,
+    <S as AsyncSeekWith<'a>>::SeekFuture: Sync,
+{
+}
+
+impl<'a, S: for<'b> AsyncSeekWith<'b> + ?Sized> CompletionFuture for CompletionStreamLen<'a, S> {
+    type Output = io::Result<u64>;
+
+    // SAFETY: we never move `self` or any of its fields out; the inner
+    // futures are only ever accessed through a re-pinned reference.
+    unsafe fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+
+        loop {
+            match &mut this.phase {
+                Phase::Position(future) => {
+                    // SAFETY: `future` is never moved while pinned.
+                    let pinned = unsafe { Pin::new_unchecked(future) };
+                    let old_pos = match unsafe { pinned.poll(cx) } {
+                        Poll::Ready(result) => result?,
+                        Poll::Pending => return Poll::Pending,
+                    };
+
+                    this.phase = Phase::Switching;
+                    let future = <S as AsyncSeekWith<'a>>::seek(this.reborrow(), SeekFrom::End(0));
+                    this.phase = Phase::SeekingEnd { future, old_pos };
+                }
+                Phase::SeekingEnd { future, old_pos } => {
+                    let old_pos = *old_pos;
+                    // SAFETY: `future` is never moved while pinned.
+                    let pinned = unsafe { Pin::new_unchecked(future) };
+                    let len = match unsafe { pinned.poll(cx) } {
+                        Poll::Ready(result) => result?,
+                        Poll::Pending => return Poll::Pending,
+                    };
+
+                    // Avoid seeking a third time when we were already at the
+                    // end of the stream. The branch is usually way cheaper
+                    // than a seek operation.
+                    if old_pos == len {
+                        return Poll::Ready(Ok(len));
+                    }
+
+                    this.phase = Phase::Switching;
+                    let future =
+                        <S as AsyncSeekWith<'a>>::seek(this.reborrow(), SeekFrom::Start(old_pos));
+                    this.phase = Phase::Restoring { future, len };
+                }
+                Phase::Restoring { future, len } => {
+                    let len = *len;
+                    // SAFETY: `future` is never moved while pinned.
+                    let pinned = unsafe { Pin::new_unchecked(future) };
+                    match unsafe { pinned.poll(cx) } {
+                        Poll::Ready(result) => {
+                            result?;
+                        }
+                        Poll::Pending => return Poll::Pending,
+                    }
+                    return Poll::Ready(Ok(len));
+                }
+                Phase::Switching => unreachable!("polled between two phases"),
+            }
+        }
+    }
+
+    unsafe fn poll_cancel(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = unsafe { self.get_unchecked_mut() };
+        let future = match &mut this.phase {
+            Phase::Position(future) => future,
+            Phase::SeekingEnd { future, .. } => future,
+            Phase::Restoring { future, .. } => future,
+            Phase::Switching => return Poll::Ready(()),
+        };
+        // SAFETY: `future` is never moved while pinned.
+        unsafe { Pin::new_unchecked(future).poll_cancel(cx) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CompletionStreamLen;
+    use crate::completion_async_seek_ext::AsyncSeekWith;
+    use completion_core::CompletionFuture;
+    use std::{
+        io::{self, Cursor, Seek, SeekFrom},
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    /// A completion-based seek future over a `std::io::Cursor` that returns
+    /// `Poll::Pending` once (waking itself) before resolving, to make sure
+    /// `CompletionStreamLen` re-polls a single phase's future to completion
+    /// instead of abandoning it partway through.
+    struct FlakySeek<'a> {
+        cursor: &'a mut Cursor<Vec<u8>>,
+        pos: SeekFrom,
+        polled: bool,
+    }
+
+    impl CompletionFuture for FlakySeek<'_> {
+        type Output = io::Result<u64>;
+
+        unsafe fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let this = unsafe { self.get_unchecked_mut() };
+            if !this.polled {
+                this.polled = true;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            Poll::Ready(Seek::seek(this.cursor, this.pos))
+        }
+
+        unsafe fn poll_cancel(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+            Poll::Ready(())
+        }
+    }
+
+    struct Flaky(Cursor<Vec<u8>>);
+
+    impl<'a> AsyncSeekWith<'a> for Flaky {
+        type SeekFuture = FlakySeek<'a>;
+
+        fn seek(&'a mut self, pos: SeekFrom) -> Self::SeekFuture {
+            FlakySeek {
+                cursor: &mut self.0,
+                pos,
+                polled: false,
+            }
+        }
+    }
+
+    /// A minimal, single-threaded executor: `CompletionFuture::poll` is
+    /// unsafe only because of its cancellation contract, which this helper
+    /// upholds by always driving the future to `Ready` before returning.
+    fn block_on<F: CompletionFuture>(mut future: F) -> F::Output {
+        use std::task::{RawWaker, RawWakerVTable, Waker};
+
+        fn noop(_: *const ()) {}
+        fn clone(_: *const ()) -> RawWaker {
+            RawWaker::new(std::ptr::null(), &VTABLE)
+        }
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+        let waker = unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) };
+        let mut cx = Context::from_waker(&waker);
+
+        // SAFETY: `future` is never moved again after this point.
+        let mut future = unsafe { Pin::new_unchecked(&mut future) };
+        loop {
+            // SAFETY: `future` is driven to `Ready` before being dropped.
+            match unsafe { future.as_mut().poll(&mut cx) } {
+                Poll::Ready(output) => return output,
+                Poll::Pending => {}
+            }
+        }
+    }
+
+    #[test]
+    fn stream_len() -> io::Result<()> {
+        let mut flaky = Flaky(Cursor::new(vec![0; 15]));
+        assert_eq!(block_on(CompletionStreamLen::new(&mut flaky))?, 15);
+        assert_eq!(flaky.0.position(), 0);
+
+        flaky.0.set_position(7);
+        assert_eq!(block_on(CompletionStreamLen::new(&mut flaky))?, 15);
+        assert_eq!(flaky.0.position(), 7);
+        Ok(())
+    }
+
+    #[test]
+    fn stream_len_at_end() -> io::Result<()> {
+        let mut flaky = Flaky(Cursor::new(vec![0; 15]));
+        flaky.0.set_position(15);
+
+        assert_eq!(block_on(CompletionStreamLen::new(&mut flaky))?, 15);
+        assert_eq!(flaky.0.position(), 15);
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+use crate::completion_async_seek_ext::AsyncSeekWith;
+use completion_core::CompletionFuture;
+use std::{
+    io::{self, SeekFrom},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct CompletionStreamPosition<'a, S: for<'b> AsyncSeekWith<'b> + ?Sized> {
+    future: <S as AsyncSeekWith<'a>>::SeekFuture,
+}
+
+impl<'a, S: for<'b> AsyncSeekWith<'b> + ?Sized> CompletionStreamPosition<'a, S> {
+    pub(super) fn new(seek: &'a mut S) -> Self {
+        Self {
+            future: seek.seek(SeekFrom::Current(0)),
+        }
+    }
+}
+
+impl<'a, S: for<'b> AsyncSeekWith<'b> + ?Sized> CompletionFuture for CompletionStreamPosition<'a, S> {
+    type Output = io::Result<u64>;
+
+    // SAFETY: polling forwards directly to the inner completion future, so
+    // the "must be polled to completion, never dropped mid-flight" contract
+    // carries over unchanged.
+    unsafe fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let future = unsafe { self.map_unchecked_mut(|this| &mut this.future) };
+        unsafe { future.poll(cx) }
+    }
+
+    unsafe fn poll_cancel(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let future = unsafe { self.map_unchecked_mut(|this| &mut this.future) };
+        unsafe { future.poll_cancel(cx) }
+    }
+}
@@ -1,8 +1,14 @@
+#![cfg_attr(feature = "specialization", feature(specialization))]
+
 #[allow(unstable_name_collisions)]
 #[cfg(feature="std")]
 pub mod seek_ext;
 #[cfg(feature="std")]
 pub use crate::seek_ext::SeekExt;
+#[cfg(feature="std")]
+pub mod seek_meta;
+#[cfg(feature="std")]
+pub use crate::seek_meta::SeekMeta;
 
 #[cfg(feature="async")]
 pub mod async_seek_ext;
@@ -12,3 +18,21 @@ pub use async_seek_ext::AsyncSeekExt;
 mod stream_len;
 #[cfg(feature="async")]
 mod stream_position;
+
+#[cfg(feature="tokio")]
+pub mod tokio_async_seek_ext;
+#[cfg(feature="tokio")]
+pub use tokio_async_seek_ext::TokioAsyncSeekExt;
+#[cfg(feature="tokio")]
+mod tokio_stream_len;
+#[cfg(feature="tokio")]
+mod tokio_stream_position;
+
+#[cfg(feature="completion")]
+pub mod completion_async_seek_ext;
+#[cfg(feature="completion")]
+pub use completion_async_seek_ext::{AsyncSeekWith, CompletionSeekExt};
+#[cfg(feature="completion")]
+mod completion_stream_len;
+#[cfg(feature="completion")]
+mod completion_stream_position;
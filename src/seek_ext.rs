@@ -3,6 +3,30 @@
 
 use std::io::{Result, Seek, SeekFrom};
 
+#[cfg(feature = "specialization")]
+use crate::seek_meta::SeekMeta;
+
+/// The seek-based implementation shared by every `stream_len` in this
+/// module, regardless of whether a faster native answer was available.
+fn seek_based_stream_len<T: Seek + ?Sized>(this: &mut T) -> Result<u64> {
+    let old_pos = seek_based_stream_position(this)?;
+    let len = this.seek(SeekFrom::End(0))?;
+
+    // Avoid seeking a third time when we were already at the end of the
+    // stream. The branch is usually way cheaper than a seek operation.
+    if old_pos != len {
+        this.seek(SeekFrom::Start(old_pos))?;
+    }
+
+    Ok(len)
+}
+
+/// The seek-based implementation shared by every `stream_position` in this
+/// module, regardless of whether a faster native answer was available.
+fn seek_based_stream_position<T: Seek + ?Sized>(this: &mut T) -> Result<u64> {
+    this.seek(SeekFrom::Current(0))
+}
+
 /// Adds convenience methods to all types that implement `io::Seek`.
 ///
 /// This is an extension trait that has a blanket impl which implements this
@@ -38,16 +62,7 @@ pub trait SeekExt: Seek {
     /// # }
     /// ```
     fn stream_len(&mut self) -> Result<u64> {
-        let old_pos = self.stream_position()?;
-        let len = self.seek(SeekFrom::End(0))?;
-
-        // Avoid seeking a third time when we were already at the end of the
-        // stream. The branch is usually way cheaper than a seek operation.
-        if old_pos != len {
-            self.seek(SeekFrom::Start(old_pos))?;
-        }
-
-        Ok(len)
+        seek_based_stream_len(self)
     }
 
     /// Returns the current seek position from the start of the stream.
@@ -73,12 +88,44 @@ pub trait SeekExt: Seek {
     /// # }
     /// ```
     fn stream_position(&mut self) -> Result<u64> {
-        self.seek(SeekFrom::Current(0))
+        seek_based_stream_position(self)
     }
 }
 
+#[cfg(not(feature = "specialization"))]
 impl<T: Seek> SeekExt for T {}
 
+// With the `specialization` feature, prefer a type's native `SeekMeta`
+// answer over the seek dance in the default methods above, cutting a
+// `Cursor`'s `stream_len`/`stream_position` down to zero seeks.
+#[cfg(feature = "specialization")]
+impl<T: Seek> SeekExt for T {
+    default fn stream_len(&mut self) -> Result<u64> {
+        seek_based_stream_len(self)
+    }
+
+    default fn stream_position(&mut self) -> Result<u64> {
+        seek_based_stream_position(self)
+    }
+}
+
+#[cfg(feature = "specialization")]
+impl<T: SeekMeta> SeekExt for T {
+    fn stream_len(&mut self) -> Result<u64> {
+        match SeekMeta::stream_len_hint(self) {
+            Some(len) => Ok(len),
+            None => seek_based_stream_len(self),
+        }
+    }
+
+    fn stream_position(&mut self) -> Result<u64> {
+        match SeekMeta::stream_position_hint(self) {
+            Some(pos) => Ok(pos),
+            None => seek_based_stream_position(self),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::SeekExt;
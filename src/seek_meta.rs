@@ -0,0 +1,64 @@
+//! Adds the [`SeekMeta`] extension point, which lets `io::Seek` types report
+//! their length or position without performing a seek.
+
+use std::io::{Cursor, Seek};
+
+/// Lets a `Seek` type report its length or position directly, instead of
+/// going through a seek.
+///
+/// [`SeekExt::stream_len`] and [`SeekExt::stream_position`] prefer these
+/// native answers over the seek-based default when they're available (via
+/// the `specialization` feature), which avoids the seek dance entirely for
+/// types such as [`Cursor`] that track their length and position as plain
+/// fields.
+///
+/// [`SeekExt::stream_len`]: crate::SeekExt::stream_len
+/// [`SeekExt::stream_position`]: crate::SeekExt::stream_position
+pub trait SeekMeta: Seek {
+    /// Returns the length of the stream, if it can be determined without
+    /// seeking.
+    fn stream_len_hint(&self) -> Option<u64> {
+        None
+    }
+
+    /// Returns the current position in the stream, if it can be determined
+    /// without seeking.
+    fn stream_position_hint(&self) -> Option<u64> {
+        None
+    }
+}
+
+impl<T: AsRef<[u8]>> SeekMeta for Cursor<T> {
+    fn stream_len_hint(&self) -> Option<u64> {
+        Some(self.get_ref().as_ref().len() as u64)
+    }
+
+    fn stream_position_hint(&self) -> Option<u64> {
+        Some(self.position())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SeekMeta;
+    use std::io::{Cursor, Seek, SeekFrom};
+
+    #[test]
+    fn cursor_hints() -> std::io::Result<()> {
+        let mut c = Cursor::new(vec![0; 15]);
+        assert_eq!(c.stream_len_hint(), Some(15));
+        assert_eq!(c.stream_position_hint(), Some(0));
+
+        c.seek(SeekFrom::Start(7))?;
+        assert_eq!(c.stream_len_hint(), Some(15));
+        assert_eq!(c.stream_position_hint(), Some(7));
+        Ok(())
+    }
+
+    #[test]
+    fn slice_cursor_hints() {
+        let c = Cursor::new(&b"hello"[..]);
+        assert_eq!(c.stream_len_hint(), Some(5));
+        assert_eq!(c.stream_position_hint(), Some(0));
+    }
+}
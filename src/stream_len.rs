@@ -1,22 +1,38 @@
-use crate::async_seek_ext::AsyncSeekExt;
-use futures::{future::Future, io::AsyncSeek, ready};
+use futures::{future::Future, io::AsyncSeek};
 use std::{
     io::{self, SeekFrom},
     pin::Pin,
     task::{Context, Poll},
 };
 
+/// The phase of the seek dance `StreamLen` is currently driving.
+///
+/// `futures::io::AsyncSeek::poll_seek` requires that once a seek for a given
+/// `SeekFrom` returns `Poll::Pending`, it is re-polled with that *same*
+/// `SeekFrom` until it resolves, so each phase below must be polled to
+/// completion before the next one starts.
+#[derive(Debug)]
+enum Phase {
+    ReadingPosition,
+    SeekingEnd { old_pos: u64 },
+    Restoring { old_pos: u64, len: u64 },
+}
+
 #[derive(Debug)]
 #[must_use = "futures do nothing unless you `.await` or poll them"]
 pub struct StreamLen<'a, S: ?Sized> {
     seek: &'a mut S,
+    phase: Phase,
 }
 
 impl<S: ?Sized + Unpin> Unpin for StreamLen<'_, S> {}
 
 impl<'a, S: AsyncSeek + ?Sized + Unpin> StreamLen<'a, S> {
     pub(super) fn new(seek: &'a mut S) -> Self {
-        Self { seek }
+        Self {
+            seek,
+            phase: Phase::ReadingPosition,
+        }
     }
 }
 
@@ -24,14 +40,111 @@ impl<S: AsyncSeek + ?Sized + Unpin> Future for StreamLen<'_, S> {
     type Output = io::Result<u64>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let this = &mut *self;
-        let old_pos = ready!(Pin::new(&mut this.seek.stream_position()).poll(cx)?);
-        let len = ready!(Pin::new(&mut this.seek).poll_seek(cx, SeekFrom::End(0))?);
+        loop {
+            let this = &mut *self;
+
+            match this.phase {
+                Phase::ReadingPosition => {
+                    let old_pos =
+                        match Pin::new(&mut *this.seek).poll_seek(cx, SeekFrom::Current(0)) {
+                            Poll::Ready(result) => result?,
+                            Poll::Pending => return Poll::Pending,
+                        };
+                    this.phase = Phase::SeekingEnd { old_pos };
+                }
+                Phase::SeekingEnd { old_pos } => {
+                    let len = match Pin::new(&mut *this.seek).poll_seek(cx, SeekFrom::End(0)) {
+                        Poll::Ready(result) => result?,
+                        Poll::Pending => return Poll::Pending,
+                    };
+
+                    // Avoid seeking a third time when we were already at the
+                    // end of the stream. The branch is usually way cheaper
+                    // than a seek operation.
+                    if old_pos == len {
+                        return Poll::Ready(Ok(len));
+                    }
+                    this.phase = Phase::Restoring { old_pos, len };
+                }
+                Phase::Restoring { old_pos, len } => {
+                    match Pin::new(&mut *this.seek).poll_seek(cx, SeekFrom::Start(old_pos)) {
+                        Poll::Ready(result) => {
+                            result?;
+                        }
+                        Poll::Pending => return Poll::Pending,
+                    }
+                    return Poll::Ready(Ok(len));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StreamLen;
+    use futures::{
+        executor::block_on,
+        io::{AsyncSeek, Cursor},
+    };
+    use std::{
+        io::{self, SeekFrom},
+        pin::Pin,
+        task::{Context, Poll},
+    };
 
-        if old_pos != len {
-            ready!(Pin::new(&mut this.seek).poll_seek(cx, SeekFrom::Start(old_pos))?);
+    /// Wraps an `AsyncSeek` and returns `Poll::Pending` on every other call
+    /// to `poll_seek`, to make sure `StreamLen` re-polls a seek that is still
+    /// in progress with the same `SeekFrom` instead of starting a new one.
+    struct Flaky<S> {
+        inner: S,
+        pending: bool,
+    }
+
+    impl<S> Flaky<S> {
+        fn new(inner: S) -> Self {
+            Self {
+                inner,
+                pending: false,
+            }
+        }
+    }
+
+    impl<S: AsyncSeek + Unpin> AsyncSeek for Flaky<S> {
+        fn poll_seek(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            pos: SeekFrom,
+        ) -> Poll<io::Result<u64>> {
+            if !self.pending {
+                self.pending = true;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            self.pending = false;
+            Pin::new(&mut self.inner).poll_seek(cx, pos)
         }
+    }
+
+    #[test]
+    fn stream_len_pending_every_other_poll() -> io::Result<()> {
+        let mut inner = Cursor::new(vec![0; 15]);
+        inner.set_position(7);
+        let mut c = Flaky::new(inner);
+
+        assert_eq!(block_on(StreamLen::new(&mut c))?, 15);
+        assert_eq!(c.inner.position(), 7);
+        Ok(())
+    }
+
+    #[test]
+    fn stream_len_pending_every_other_poll_at_end() -> io::Result<()> {
+        let mut inner = Cursor::new(vec![0; 15]);
+        inner.set_position(15);
+        let mut c = Flaky::new(inner);
 
-        Poll::Ready(Ok(len))
+        assert_eq!(block_on(StreamLen::new(&mut c))?, 15);
+        assert_eq!(c.inner.position(), 15);
+        Ok(())
     }
 }
@@ -0,0 +1,78 @@
+use crate::{tokio_stream_len::TokioStreamLen, tokio_stream_position::TokioStreamPosition};
+use tokio::io::AsyncSeek;
+
+/// Adds convenience methods to `tokio::io::AsyncSeek` types via the
+/// [`TokioAsyncSeekExt`] extension trait.
+///
+/// The methods are named `tokio_stream_len`/`tokio_stream_position` rather
+/// than `stream_len`/`stream_position` because `tokio::io::AsyncSeekExt`
+/// already provides methods with those names on the exact same `S:
+/// AsyncSeek + Unpin` bound; importing both traits would otherwise make
+/// every call ambiguous.
+pub trait TokioAsyncSeekExt: AsyncSeek {
+    fn tokio_stream_position(&mut self) -> TokioStreamPosition<'_, Self>
+    where
+        Self: Unpin,
+    {
+        TokioStreamPosition::new(self)
+    }
+
+    fn tokio_stream_len(&mut self) -> TokioStreamLen<'_, Self>
+    where
+        Self: Unpin,
+    {
+        TokioStreamLen::new(self)
+    }
+}
+
+impl<S: AsyncSeek + ?Sized> TokioAsyncSeekExt for S {}
+
+#[cfg(test)]
+mod tests {
+    use super::TokioAsyncSeekExt;
+    use std::io;
+    use tokio::io::{AsyncSeekExt as TokioSeekExt, SeekFrom};
+
+    #[tokio::test]
+    async fn stream_len() -> io::Result<()> {
+        let mut c = io::Cursor::new(vec![0; 15]);
+        assert_eq!(c.tokio_stream_len().await?, 15);
+
+        c.seek(SeekFrom::End(0)).await?;
+        let old_pos = c.tokio_stream_position().await?;
+        assert_eq!(c.tokio_stream_len().await?, 15);
+        assert_eq!(c.tokio_stream_position().await?, old_pos);
+
+        c.seek(SeekFrom::Start(7)).await?;
+        c.seek(SeekFrom::Current(2)).await?;
+        let old_pos = c.tokio_stream_position().await?;
+        assert_eq!(c.tokio_stream_len().await?, 15);
+        assert_eq!(c.tokio_stream_position().await?, old_pos);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn stream_position() -> io::Result<()> {
+        // All `asserts` are duplicated here to make sure the method does not
+        // change anything about the seek state.
+        let mut c = io::Cursor::new(vec![0; 15]);
+        assert_eq!(c.tokio_stream_position().await?, 0);
+        assert_eq!(c.tokio_stream_position().await?, 0);
+
+        c.seek(SeekFrom::End(0)).await?;
+        assert_eq!(c.tokio_stream_position().await?, 15);
+        assert_eq!(c.tokio_stream_position().await?, 15);
+
+        c.seek(SeekFrom::Start(7)).await?;
+        c.seek(SeekFrom::Current(2)).await?;
+        assert_eq!(c.tokio_stream_position().await?, 9);
+        assert_eq!(c.tokio_stream_position().await?, 9);
+
+        c.seek(SeekFrom::End(-3)).await?;
+        c.seek(SeekFrom::Current(1)).await?;
+        c.seek(SeekFrom::Current(-5)).await?;
+        assert_eq!(c.tokio_stream_position().await?, 8);
+        assert_eq!(c.tokio_stream_position().await?, 8);
+        Ok(())
+    }
+}
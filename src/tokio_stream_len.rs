@@ -0,0 +1,178 @@
+use std::{
+    future::Future,
+    io::{self, SeekFrom},
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::io::AsyncSeek;
+
+/// The phase of the seek dance `TokioStreamLen` is currently driving.
+///
+/// `tokio::io::AsyncSeek` is a two-phase API: a `start_seek` may only be
+/// issued once the previous seek's `poll_complete` has returned `Ready`, so
+/// each phase below is polled to completion before the next one starts.
+#[derive(Debug)]
+enum Phase {
+    Position,
+    SeekingEnd,
+    Restoring,
+}
+
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct TokioStreamLen<'a, S: ?Sized> {
+    seek: &'a mut S,
+    phase: Phase,
+    started: bool,
+    old_pos: u64,
+    len: u64,
+}
+
+impl<S: ?Sized + Unpin> Unpin for TokioStreamLen<'_, S> {}
+
+impl<'a, S: AsyncSeek + ?Sized + Unpin> TokioStreamLen<'a, S> {
+    pub(super) fn new(seek: &'a mut S) -> Self {
+        Self {
+            seek,
+            phase: Phase::Position,
+            started: false,
+            old_pos: 0,
+            len: 0,
+        }
+    }
+}
+
+impl<S: AsyncSeek + ?Sized + Unpin> Future for TokioStreamLen<'_, S> {
+    type Output = io::Result<u64>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let this = &mut *self;
+
+            match this.phase {
+                Phase::Position => {
+                    if !this.started {
+                        Pin::new(&mut *this.seek).start_seek(SeekFrom::Current(0))?;
+                        this.started = true;
+                    }
+
+                    this.old_pos = match Pin::new(&mut *this.seek).poll_complete(cx) {
+                        Poll::Ready(result) => result?,
+                        Poll::Pending => return Poll::Pending,
+                    };
+                    this.phase = Phase::SeekingEnd;
+                    this.started = false;
+                }
+                Phase::SeekingEnd => {
+                    if !this.started {
+                        Pin::new(&mut *this.seek).start_seek(SeekFrom::End(0))?;
+                        this.started = true;
+                    }
+
+                    this.len = match Pin::new(&mut *this.seek).poll_complete(cx) {
+                        Poll::Ready(result) => result?,
+                        Poll::Pending => return Poll::Pending,
+                    };
+
+                    // Avoid seeking a third time when we were already at the
+                    // end of the stream. The branch is usually way cheaper
+                    // than a seek operation.
+                    if this.old_pos == this.len {
+                        return Poll::Ready(Ok(this.len));
+                    }
+                    this.phase = Phase::Restoring;
+                    this.started = false;
+                }
+                Phase::Restoring => {
+                    if !this.started {
+                        Pin::new(&mut *this.seek).start_seek(SeekFrom::Start(this.old_pos))?;
+                        this.started = true;
+                    }
+
+                    match Pin::new(&mut *this.seek).poll_complete(cx) {
+                        Poll::Ready(result) => {
+                            result?;
+                        }
+                        Poll::Pending => return Poll::Pending,
+                    }
+                    return Poll::Ready(Ok(this.len));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TokioStreamLen;
+    use std::{
+        io::{self, SeekFrom},
+        pin::Pin,
+        task::{Context, Poll},
+    };
+    use tokio::io::AsyncSeek;
+
+    /// Wraps an `AsyncSeek` and makes `poll_complete` return `Pending` once
+    /// per seek (waking itself), while erroring if `start_seek` is called
+    /// again before the in-flight seek's `poll_complete` has resolved, to
+    /// make sure `TokioStreamLen` never violates that two-phase contract.
+    struct Flaky<S> {
+        inner: S,
+        in_progress: bool,
+        pending: bool,
+    }
+
+    impl<S> Flaky<S> {
+        fn new(inner: S) -> Self {
+            Self {
+                inner,
+                in_progress: false,
+                pending: false,
+            }
+        }
+    }
+
+    impl<S: AsyncSeek + Unpin> AsyncSeek for Flaky<S> {
+        fn start_seek(mut self: Pin<&mut Self>, position: SeekFrom) -> io::Result<()> {
+            if self.in_progress {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "start_seek called while a seek was already in progress",
+                ));
+            }
+            self.in_progress = true;
+            self.pending = true;
+            Pin::new(&mut self.inner).start_seek(position)
+        }
+
+        fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+            if self.pending {
+                self.pending = false;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            self.in_progress = false;
+            Pin::new(&mut self.inner).poll_complete(cx)
+        }
+    }
+
+    #[tokio::test]
+    async fn stream_len_pending_every_other_poll() -> io::Result<()> {
+        let mut c = Flaky::new(io::Cursor::new(vec![0; 15]));
+        c.inner.set_position(7);
+
+        assert_eq!(TokioStreamLen::new(&mut c).await?, 15);
+        assert_eq!(c.inner.position(), 7);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn stream_len_pending_every_other_poll_at_end() -> io::Result<()> {
+        let mut c = Flaky::new(io::Cursor::new(vec![0; 15]));
+        c.inner.set_position(15);
+
+        assert_eq!(TokioStreamLen::new(&mut c).await?, 15);
+        assert_eq!(c.inner.position(), 15);
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use std::{
+    future::Future,
+    io::{self, SeekFrom},
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::io::AsyncSeek;
+
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct TokioStreamPosition<'a, S: ?Sized> {
+    seek: &'a mut S,
+    started: bool,
+}
+
+impl<S: ?Sized + Unpin> Unpin for TokioStreamPosition<'_, S> {}
+
+impl<'a, S: AsyncSeek + ?Sized + Unpin> TokioStreamPosition<'a, S> {
+    pub(super) fn new(seek: &'a mut S) -> Self {
+        Self {
+            seek,
+            started: false,
+        }
+    }
+}
+
+impl<S: AsyncSeek + ?Sized + Unpin> Future for TokioStreamPosition<'_, S> {
+    type Output = io::Result<u64>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+
+        if !this.started {
+            Pin::new(&mut *this.seek).start_seek(SeekFrom::Current(0))?;
+            this.started = true;
+        }
+
+        Pin::new(&mut *this.seek).poll_complete(cx)
+    }
+}